@@ -16,7 +16,7 @@
 //! rdr.est_total_time()   // `std::time::Instant` when, at this rate, it'll be finished
 //! ```
 use std::path::PathBuf;
-use std::io::Read;
+use std::io::{Read, Write, IoSlice};
 use std::io::BufReader;
 use std::fs::File;
 use std::time::{Instant, Duration};
@@ -78,6 +78,13 @@ pub trait ReadWithSize: Read {
 
 }
 
+/// Default window that `recent_bytes_per_sec` and `smoothed_eta` average over.
+const DEFAULT_SMOOTHING_WINDOW: Duration = Duration::from_secs(5);
+
+/// Decay factor for the exponential moving average rate: how much weight the latest read gets,
+/// vs the existing average.
+const EMA_ALPHA: f64 = 0.3;
+
 /// A wrapper for a `Read` that monitors how many bytes have been read, and how many are to go
 pub struct ReaderWithSize<R: Read> {
     inner: R,
@@ -85,12 +92,23 @@ pub struct ReaderWithSize<R: Read> {
     total_size: usize,
     total_read: usize,
     read_start_time: Option<Instant>,
+
+    smoothing_window: Duration,
+    recent_samples: std::collections::VecDeque<(Instant, usize)>,
+    last_read_instant: Option<Instant>,
+    ema_rate: Option<f64>,
 }
 
 impl<R: Read> ReaderWithSize<R> {
     /// Create a ReaderWithSize from `inner` presuming the total number of bytes is `total_size`.
     pub fn new(total_size: usize, inner: R) -> Self {
-        ReaderWithSize{ total_size, total_read: 0, inner, read_start_time: None }
+        ReaderWithSize{
+            total_size, total_read: 0, inner, read_start_time: None,
+            smoothing_window: DEFAULT_SMOOTHING_WINDOW,
+            recent_samples: std::collections::VecDeque::new(),
+            last_read_instant: None,
+            ema_rate: None,
+        }
     }
 
     /// Consumer this, and return the inner `Read`.
@@ -103,10 +121,76 @@ impl<R: Read> ReaderWithSize<R> {
         &self.inner
     }
 
+    /// How far back `recent_bytes_per_sec` and `smoothed_eta` look when averaging recent
+    /// throughput. Defaults to 5 seconds.
+    pub fn with_smoothing_window(mut self, window: Duration) -> Self {
+        self.smoothing_window = window;
+        self
+    }
+
+    /// How many bytes per second have been read within the smoothing window, rather than
+    /// averaged over this reader's whole lifetime like `bytes_per_sec`. `None` if there isn't
+    /// enough data yet, or if no read has completed within the smoothing window (the stream has
+    /// stalled), since the last sample would otherwise report a stale pre-stall rate until the
+    /// next `read()` prunes it.
+    pub fn recent_bytes_per_sec(&self) -> Option<f64> {
+        let (oldest_time, oldest_read) = *self.recent_samples.front()?;
+        let (newest_time, newest_read) = *self.recent_samples.back()?;
+        if Instant::now() - newest_time > self.smoothing_window {
+            return None;
+        }
+        let elapsed = (newest_time - oldest_time).as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        Some(((newest_read - oldest_read) as f64)/elapsed)
+    }
+
+    /// Estimated Time to Arrival based on `recent_bytes_per_sec` rather than the lifetime
+    /// average, so it reacts to the stream's current speed instead of one that was fast then
+    /// stalled (or vice-versa) — but only once the reader has resumed; while stalled past the
+    /// smoothing window, `recent_bytes_per_sec` (and so this) returns `None` rather than the
+    /// stale pre-stall rate. `None` if there isn't enough data yet.
+    pub fn smoothed_eta(&self) -> Option<Duration> {
+        let rate = self.recent_bytes_per_sec()?;
+        if rate <= 0.0 {
+            return None;
+        }
+        let remaining = self.total_size.saturating_sub(self.total_read) as f64;
+        Some(Duration::from_secs_f64(remaining/rate))
+    }
+
+    /// An exponential moving average of bytes read per second, updated in O(1) per read rather
+    /// than scanning a window of samples. `None` if there isn't enough data yet.
+    pub fn ema_bytes_per_sec(&self) -> Option<f64> {
+        self.ema_rate
+    }
+
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         let result = self.inner.read(buf);
         if let Ok(bytes_read) = result {
             self.total_read += bytes_read;
+
+            if bytes_read > 0 {
+                let now = Instant::now();
+
+                if let Some(last_read_instant) = self.last_read_instant {
+                    let dt = (now - last_read_instant).as_secs_f64();
+                    if dt > 0.0 {
+                        let instantaneous_rate = (bytes_read as f64)/dt;
+                        self.ema_rate = Some(match self.ema_rate {
+                            Some(rate) => EMA_ALPHA*instantaneous_rate + (1. - EMA_ALPHA)*rate,
+                            None => instantaneous_rate,
+                        });
+                    }
+                }
+                self.last_read_instant = Some(now);
+
+                self.recent_samples.push_back((now, self.total_read));
+                while self.recent_samples.len() > 1 && now - self.recent_samples[0].0 > self.smoothing_window {
+                    self.recent_samples.pop_front();
+                }
+            }
         }
         if self.read_start_time.is_none() {
             self.read_start_time = Some(Instant::now());
@@ -165,6 +249,408 @@ impl ReaderWithSize<File> {
     }
 }
 
+impl<R: Read> ReaderWithSize<R> {
+    /// Wrap this reader in a `ThrottledReader` that caps throughput to `bytes_per_sec`, allowing
+    /// short bursts up to one second's worth of bytes. Useful for simulating slow links, or
+    /// being polite to a shared disk/network, and it makes `eta()` far more predictable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_sec` isn't a positive, finite number.
+    pub fn with_speed_limit(self, bytes_per_sec: f64) -> ThrottledReader<R> {
+        ThrottledReader::new(self, bytes_per_sec)
+    }
+}
+
+/// A `Read` that caps throughput to a given rate using a token bucket, while still tracking
+/// progress via the wrapped `ReaderWithSize`.
+///
+/// Tokens refill at `rate` bytes/sec up to `capacity` (one second's worth of bytes), so short
+/// bursts are allowed but long-run throughput is held at `rate`.
+pub struct ThrottledReader<R: Read> {
+    inner: ReaderWithSize<R>,
+
+    capacity: f64,
+    tokens: f64,
+    rate: f64,
+    last_refill: Instant,
+}
+
+impl<R: Read> ThrottledReader<R> {
+    /// Wrap `inner`, limiting it to `rate` bytes/sec.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` isn't a positive, finite number, since the token bucket can only pace
+    /// reads at such a rate: an infinite rate would never throttle, and a zero or negative one
+    /// would leave no valid time to wait for the next token.
+    pub fn new(inner: ReaderWithSize<R>, rate: f64) -> Self {
+        assert!(rate.is_finite() && rate > 0.0, "ThrottledReader rate must be positive and finite, got {}", rate);
+        ThrottledReader{ inner, capacity: rate, tokens: rate, rate, last_refill: Instant::now() }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = (now - self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed*self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.refill();
+
+        if self.tokens < 1.0 {
+            let wait = (1.0 - self.tokens)/self.rate;
+            std::thread::sleep(Duration::from_secs_f64(wait));
+            self.refill();
+        }
+
+        let allowed = (self.tokens.floor() as usize).min(buf.len());
+        let bytes_read = self.inner.read(&mut buf[..allowed])?;
+        self.tokens -= bytes_read as f64;
+
+        Ok(bytes_read)
+    }
+}
+
+impl<R: Read> ReadWithSize for ThrottledReader<R> {
+    fn total_read(&self) -> usize {
+        self.inner.total_read()
+    }
+
+    fn assummed_total_size(&self) -> usize {
+        self.inner.assummed_total_size()
+    }
+
+    fn fraction(&self) -> f64 {
+        self.inner.fraction()
+    }
+
+    fn read_start_time(&self) -> Option<Instant> {
+        self.inner.read_start_time()
+    }
+}
+
+
+impl<R: Read> ReaderWithSize<R> {
+    /// Wrap this reader so it stops after `limit` bytes, like `Read::take`, but keeps its own
+    /// `total_read`/timing bookkeeping over that sub-range. `assummed_total_size()` and
+    /// `fraction()`/`eta()` track progress towards `limit` rather than the whole underlying
+    /// reader, so several limited regions can be chained over one reader.
+    pub fn take_with_size(self, limit: usize) -> LimitedReaderWithSize<ReaderWithSize<R>> {
+        LimitedReaderWithSize::new(limit, self)
+    }
+}
+
+/// A `Read` that refuses to read past `limit` bytes from the inner reader, like `std::io::Take`,
+/// while tracking its own progress over that sub-range. Useful for reading a length-prefixed
+/// chunk inside a larger stream.
+pub struct LimitedReaderWithSize<R: Read> {
+    inner: R,
+
+    limit: usize,
+    total_size: usize,
+    total_read: usize,
+    read_start_time: Option<Instant>,
+}
+
+impl<R: Read> LimitedReaderWithSize<R> {
+    /// Create a LimitedReaderWithSize that will read at most `limit` bytes from `inner`.
+    pub fn new(limit: usize, inner: R) -> Self {
+        LimitedReaderWithSize{ inner, limit, total_size: limit, total_read: 0, read_start_time: None }
+    }
+
+    /// Consume this, and return the inner `Read`.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for LimitedReaderWithSize<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.limit - self.total_read;
+        if remaining == 0 {
+            return Ok(0);
+        }
+
+        let max = buf.len().min(remaining);
+        let bytes_read = self.inner.read(&mut buf[..max])?;
+        self.total_read += bytes_read;
+        if self.read_start_time.is_none() {
+            self.read_start_time = Some(Instant::now());
+        }
+
+        Ok(bytes_read)
+    }
+}
+
+impl<R: Read> ReadWithSize for LimitedReaderWithSize<R> {
+    fn total_read(&self) -> usize {
+        self.total_read
+    }
+
+    fn assummed_total_size(&self) -> usize {
+        self.total_size
+    }
+
+    fn fraction(&self) -> f64 {
+        (self.total_read as f64)/(self.total_size as f64)
+    }
+
+    fn read_start_time(&self) -> Option<Instant> {
+        self.read_start_time
+    }
+}
+
+
+pub trait WriteWithSize: Write {
+    /// The total number of bytes that have been written to this writer
+    fn total_written(&self) -> usize;
+
+    /// The assumed total number of bytes this writer will receive, created when this object was
+    /// created.
+    fn assummed_total_size(&self) -> usize;
+
+    /// How far along this writer have we written? What fraction have we written? May be >1.0 if
+    /// the initial provided assumed total size was wrong.
+    fn fraction(&self) -> f64;
+
+    /// When did this writer start writing
+    /// `None` if it hasn't started
+    fn write_start_time(&self) -> Option<Instant>;
+
+    /// Estimated Time to Arrival, at this rate, what's the predicted end time
+    /// `None` if it hasn't started yet
+    fn eta(&self) -> Option<Duration> {
+        self.write_start_time().map(|write_start_time| {
+            let duration_since_start = Instant::now() - write_start_time;
+            duration_since_start.div_f64(self.fraction()) - duration_since_start
+        })
+    }
+
+    /// Estimated Time to Completion, at this rate, how long before it is complete
+    /// `None` if it hasn't started yet
+    fn etc(&self) -> Option<Instant> {
+        self.write_start_time().map(|write_start_time| {
+            let duration_since_start = Instant::now() - write_start_time;
+            write_start_time + duration_since_start.div_f64(self.fraction())
+        })
+    }
+
+    /// How many bytes per second are being written.
+    /// `None` if it hasn't started
+    fn bytes_per_sec(&self) -> Option<f64> {
+        self.write_start_time().map(|write_start_time| {
+            let since_start = Instant::now() - write_start_time;
+            (self.total_written() as f64)/since_start.as_secs_f64()
+        })
+    }
+}
+
+/// A wrapper for a `Write` that monitors how many bytes have been written, and how many are to
+/// go, mirroring `ReaderWithSize` for the write side (e.g. copying to a file, socket or
+/// compressor of known target size).
+pub struct WriterWithSize<W: Write> {
+    inner: W,
+
+    total_size: usize,
+    total_written: usize,
+    write_start_time: Option<Instant>,
+}
+
+impl<W: Write> WriterWithSize<W> {
+    /// Create a WriterWithSize from `inner` presuming the total number of bytes to be written is
+    /// `total_size`.
+    pub fn new(total_size: usize, inner: W) -> Self {
+        WriterWithSize{ total_size, total_written: 0, inner, write_start_time: None }
+    }
+
+    /// Consume this, and return the inner `Write`.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// A reference to the inner `Write`.
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    fn record_write(&mut self, bytes_written: usize) {
+        self.total_written += bytes_written;
+        if self.write_start_time.is_none() {
+            self.write_start_time = Some(Instant::now());
+        }
+    }
+}
+
+impl<W: Write> WriteWithSize for WriterWithSize<W> {
+    fn total_written(&self) -> usize {
+        self.total_written
+    }
+
+    fn assummed_total_size(&self) -> usize {
+        self.total_size
+    }
+
+    fn fraction(&self) -> f64 {
+        (self.total_written as f64)/(self.total_size as f64)
+    }
+
+    fn write_start_time(&self) -> Option<Instant> {
+        self.write_start_time
+    }
+}
+
+impl<W: Write> Write for WriterWithSize<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let result = self.inner.write(buf)?;
+        self.record_write(result);
+        Ok(result)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+        let result = self.inner.write_vectored(bufs)?;
+        self.record_write(result);
+        Ok(result)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl WriterWithSize<File> {
+    /// Given a path, create a `WriterWithSize` based on the given target size, creating the file
+    /// if necessary.
+    pub fn from_path(path: impl Into<PathBuf>, total_size: usize) -> Result<Self, std::io::Error> {
+        let path: PathBuf = path.into();
+
+        let file = File::create(path)?;
+        Ok(Self::new(total_size, file))
+    }
+}
+
+
+/// A wrapper for a `tokio::io::AsyncRead` that monitors how many bytes have been read, and how
+/// many are to go.
+///
+/// This mirrors `ReaderWithSize`, but for async readers. It doesn't implement `ReadWithSize`
+/// (that trait requires `std::io::Read`), instead it exposes the same `fraction`, `eta`, `etc`
+/// and `bytes_per_sec` methods directly.
+///
+/// Requires the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub struct AsyncReaderWithSize<R> {
+    inner: R,
+
+    total_size: usize,
+    total_read: usize,
+    read_start_time: Option<Instant>,
+}
+
+#[cfg(feature = "tokio")]
+impl<R> AsyncReaderWithSize<R> {
+    /// Create an AsyncReaderWithSize from `inner` presuming the total number of bytes is `total_size`.
+    pub fn new(total_size: usize, inner: R) -> Self {
+        AsyncReaderWithSize{ total_size, total_read: 0, inner, read_start_time: None }
+    }
+
+    /// Consume this, and return the inner `AsyncRead`.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// A reference to the inner `AsyncRead`.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// The total number of bytes that have been read from this reader
+    pub fn total_read(&self) -> usize {
+        self.total_read
+    }
+
+    /// The assumed total number of bytes in this reader, created when this object was created.
+    pub fn assummed_total_size(&self) -> usize {
+        self.total_size
+    }
+
+    /// How far along this reader have we read? What fraction have we read? May be >1.0 if the
+    /// initial provided assumed total size was wrong.
+    pub fn fraction(&self) -> f64 {
+        (self.total_read as f64)/(self.total_size as f64)
+    }
+
+    /// When did this reader start reading
+    /// `None` if it hasn't started
+    pub fn read_start_time(&self) -> Option<Instant> {
+        self.read_start_time
+    }
+
+    /// Estimated Time to Arrival, at this rate, what's the predicted end time
+    /// `None` if it hasn't started yet
+    pub fn eta(&self) -> Option<Duration> {
+        self.read_start_time.map(|read_start_time| {
+            let duration_since_start = Instant::now() - read_start_time;
+            duration_since_start.div_f64(self.fraction()) - duration_since_start
+        })
+    }
+
+    /// Estimated Time to Completion, at this rate, how long before it is complete
+    /// `None` if it hasn't started yet
+    pub fn etc(&self) -> Option<Instant> {
+        self.read_start_time.map(|read_start_time| {
+            let duration_since_start = Instant::now() - read_start_time;
+            read_start_time + duration_since_start.div_f64(self.fraction())
+        })
+    }
+
+    /// How many bytes per second are being read.
+    /// `None` if it hasn't started
+    pub fn bytes_per_sec(&self) -> Option<f64> {
+        self.read_start_time.map(|read_start_time| {
+            let since_start = Instant::now() - read_start_time;
+            (self.total_read as f64)/since_start.as_secs_f64()
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncReaderWithSize<tokio::fs::File> {
+    /// Given an async file, create an `AsyncReaderWithSize` based on that file's size
+    pub async fn from_file(file: tokio::fs::File) -> std::io::Result<Self> {
+        let size = file.metadata().await?.len() as usize;
+
+        Ok(Self::new(size, file))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for AsyncReaderWithSize<R> {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let before = buf.filled().len();
+        let result = std::pin::Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let std::task::Poll::Ready(Ok(())) = result {
+            let bytes_read = buf.filled().len() - before;
+            if bytes_read > 0 && self.read_start_time.is_none() {
+                self.read_start_time = Some(Instant::now());
+            }
+            self.total_read += bytes_read;
+        }
+        result
+    }
+}
+
 
 pub struct BufReaderWithSize<R: Read>(BufReader<ReaderWithSize<R>>);
 
@@ -196,6 +682,37 @@ impl<R: Read> Read for BufReaderWithSize<R> {
     }
 }
 
+impl<R: Read> std::io::BufRead for BufReaderWithSize<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.0.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.0.consume(amt)
+    }
+}
+
+impl<R: Read> ReadWithSize for BufReaderWithSize<R> {
+    /// The total number of bytes pulled from the underlying file, which may run ahead of what
+    /// the caller has actually consumed out of the buffer via `read`/`read_line`/etc, since
+    /// `BufReader` fills its buffer in one go.
+    fn total_read(&self) -> usize {
+        self.0.get_ref().total_read()
+    }
+
+    fn assummed_total_size(&self) -> usize {
+        self.0.get_ref().assummed_total_size()
+    }
+
+    fn fraction(&self) -> f64 {
+        self.0.get_ref().fraction()
+    }
+
+    fn read_start_time(&self) -> Option<Instant> {
+        self.0.get_ref().read_start_time()
+    }
+}
+
 
 
 
@@ -267,4 +784,174 @@ mod tests {
         assert!(etc < start+Duration::from_secs(1));
     }
 
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_basic() {
+        use tokio::io::AsyncReadExt;
+
+        let bytes = "hello".as_bytes();
+        let mut reader = AsyncReaderWithSize::new(5, bytes);
+        assert_eq!(reader.assummed_total_size(), 5);
+        assert_eq!(reader.read_start_time(), None);
+
+        let mut buf = [0; 2];
+        reader.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"he");
+        assert_eq!(reader.total_read(), 2);
+        assert_eq!(reader.fraction(), 0.4);
+        assert!(reader.read_start_time().is_some());
+    }
+
+    #[test]
+    fn throttled_reader() {
+        let bytes = vec![0u8; 100];
+        let reader = ReaderWithSize::new(bytes.len(), Cursor::new(bytes));
+        let mut reader = reader.with_speed_limit(1000.);
+
+        let mut buf = vec![0; 100];
+        let start = Instant::now();
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(reader.total_read(), 100);
+        assert_eq!(reader.assummed_total_size(), 100);
+        assert_eq!(reader.fraction(), 1.0);
+        // 100 bytes at 1000 bytes/sec, with a 1000-byte burst capacity, fits in the initial
+        // burst so this shouldn't have needed to sleep.
+        assert!(Instant::now() - start < Duration::from_millis(100));
+    }
+
+    #[test]
+    #[should_panic]
+    fn throttled_reader_rejects_non_positive_rate() {
+        let bytes = vec![0u8; 10];
+        let reader = ReaderWithSize::new(bytes.len(), Cursor::new(bytes));
+        reader.with_speed_limit(0.0);
+    }
+
+    #[test]
+    fn throttled_reader_empty_buf_does_not_block() {
+        let bytes = vec![0u8; 10];
+        let reader = ReaderWithSize::new(bytes.len(), Cursor::new(bytes));
+        let mut reader = reader.with_speed_limit(1.);
+
+        // Drain the initial burst token.
+        let mut buf = [0; 1];
+        reader.read_exact(&mut buf).unwrap();
+
+        let start = Instant::now();
+        assert_eq!(reader.read(&mut []).unwrap(), 0);
+        assert!(Instant::now() - start < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn writer_basic() {
+        let mut writer = WriterWithSize::new(5, Vec::new());
+        assert_eq!(writer.assummed_total_size(), 5);
+        assert_eq!(writer.write_start_time(), None);
+
+        writer.write_all(b"he").unwrap();
+        assert_eq!(writer.total_written(), 2);
+        assert_eq!(writer.fraction(), 0.4);
+        assert!(writer.write_start_time().is_some());
+
+        writer.write_all(b"llo").unwrap();
+        assert_eq!(writer.total_written(), 5);
+        assert_eq!(writer.fraction(), 1.0);
+        assert_eq!(writer.into_inner(), b"hello");
+    }
+
+    #[test]
+    fn limited_reader() {
+        let bytes = "helloworld".as_bytes();
+        let mut reader = LimitedReaderWithSize::new(5, Cursor::new(bytes));
+        assert_eq!(reader.assummed_total_size(), 5);
+
+        let mut buf = Vec::new();
+        let bytes_read = reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(bytes_read, 5);
+        assert_eq!(buf, b"hello");
+        assert_eq!(reader.total_read(), 5);
+        assert_eq!(reader.fraction(), 1.0);
+
+        // The limit is hit, so further reads are a clean EOF, even though the underlying
+        // reader has more to give.
+        let mut buf = [0; 1];
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn take_with_size_chaining() {
+        let bytes = "helloworld".as_bytes();
+        let reader = ReaderWithSize::new(10, Cursor::new(bytes));
+        let mut first = reader.take_with_size(5);
+
+        let mut buf = Vec::new();
+        first.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"hello");
+
+        let reader = first.into_inner();
+        let mut second = reader.take_with_size(5);
+        let mut buf = Vec::new();
+        second.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"world");
+    }
+
+    #[test]
+    fn buf_reader_with_size() {
+        use std::io::BufRead;
+
+        let bytes = "hello\nworld".as_bytes();
+        let rdr = ReaderWithSize::new(bytes.len(), Cursor::new(bytes));
+        let mut rdr = BufReaderWithSize(BufReader::new(rdr));
+
+        let mut line = String::new();
+        rdr.read_line(&mut line).unwrap();
+        assert_eq!(line, "hello\n");
+
+        // The BufReader pulled the whole buffer from the inner reader in one go, so total_read
+        // has already run ahead of what the caller has consumed via read_line.
+        assert_eq!(rdr.total_read(), bytes.len());
+        assert_eq!(rdr.fraction(), 1.0);
+    }
+
+    #[test]
+    fn smoothed_throughput() {
+        let bytes = vec![0u8; 10];
+        let mut reader = ReaderWithSize::new(10, Cursor::new(bytes));
+
+        // No reads yet, nothing to report.
+        assert_eq!(reader.recent_bytes_per_sec(), None);
+        assert_eq!(reader.smoothed_eta(), None);
+        assert_eq!(reader.ema_bytes_per_sec(), None);
+
+        let mut buf = [0; 5];
+        reader.read_exact(&mut buf).unwrap();
+        sleep(Duration::from_millis(10));
+        let mut buf = [0; 5];
+        reader.read_exact(&mut buf).unwrap();
+
+        assert!(reader.recent_bytes_per_sec().unwrap() > 0.);
+        assert!(reader.smoothed_eta().is_some());
+        assert!(reader.ema_bytes_per_sec().unwrap() > 0.);
+    }
+
+    #[test]
+    fn stalled_recent_throughput_is_stale() {
+        let bytes = vec![0u8; 10];
+        let mut reader = ReaderWithSize::new(10, Cursor::new(bytes))
+            .with_smoothing_window(Duration::from_millis(20));
+
+        let mut buf = [0; 2];
+        reader.read_exact(&mut buf).unwrap();
+        sleep(Duration::from_millis(5));
+        let mut buf = [0; 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert!(reader.recent_bytes_per_sec().is_some());
+
+        // The stream stalls for longer than the smoothing window: the last sample is now
+        // stale, so this should report `None` rather than the last pre-stall rate.
+        sleep(Duration::from_millis(30));
+        assert_eq!(reader.recent_bytes_per_sec(), None);
+        assert_eq!(reader.smoothed_eta(), None);
+    }
+
 }